@@ -0,0 +1,91 @@
+//! A stand-in for the real `forc pkg` build pipeline.
+//!
+//! There is no Sway compiler or FuelVM bytecode available to this crate,
+//! so this lowers a `.sw` file directly into the synthetic instruction
+//! stream `DebugVm` steps through: one instruction per executable
+//! statement, in source order, each tagged with the line it came from so
+//! breakpoints and stack traces still line up with the real file. It is
+//! intentionally not a real compiler — it understands just enough of
+//! Sway's surface shape (function signatures, `let` bindings, calls,
+//! `revert`/`require`) to drive the debugger end to end.
+
+use crate::vm::Instruction;
+use std::collections::HashMap;
+
+/// Lowers Sway `source` into a synthetic instruction stream and a
+/// function-name -> entry-pc symbol table.
+pub fn build_program(source: &str) -> (Vec<Instruction>, HashMap<String, u64>) {
+    let mut instructions = Vec::new();
+    let mut symbols = HashMap::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_no = index as i64 + 1;
+        let trimmed = line.trim();
+
+        if let Some(name) = function_name(trimmed) {
+            symbols.insert(name, instructions.len() as u64);
+            continue;
+        }
+        if !is_statement(trimmed) {
+            continue;
+        }
+
+        let opcode = if trimmed.contains("revert(") || trimmed.contains("require(") {
+            "RVRT"
+        } else if calls_known_function(trimmed, &symbols) {
+            "CALL"
+        } else if trimmed.starts_with("let ") {
+            "MOVE"
+        } else {
+            "RET"
+        };
+
+        instructions.push(Instruction {
+            pc: instructions.len() as u64,
+            opcode: opcode.into(),
+            raw: 0,
+            source_line: Some(line_no),
+        });
+    }
+
+    (instructions, symbols)
+}
+
+/// Extracts the function name out of a `fn name(...)` signature line.
+fn function_name(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("fn ")?;
+    let end = rest.find('(')?;
+    Some(rest[..end].trim().to_string())
+}
+
+/// Whether `line` calls a function already seen earlier in the file, i.e.
+/// contains `name(` for some `name` already recorded in `symbols`.
+fn calls_known_function(line: &str, symbols: &HashMap<String, u64>) -> bool {
+    symbols
+        .keys()
+        .any(|name| line.contains(&format!("{name}(")))
+}
+
+/// Whether `line` is an executable statement rather than a declaration,
+/// comment, brace, or blank line.
+fn is_statement(line: &str) -> bool {
+    if line.is_empty() || line.starts_with("//") {
+        return false;
+    }
+    const NON_STATEMENT_PREFIXES: &[&str] = &[
+        "script;",
+        "contract;",
+        "predicate;",
+        "library",
+        "fn ",
+        "use ",
+        "mod ",
+        "impl ",
+        "abi ",
+        "storage",
+    ];
+    if NON_STATEMENT_PREFIXES.iter().any(|p| line.starts_with(p)) {
+        return false;
+    }
+    !matches!(line, "{" | "}")
+}
@@ -0,0 +1,165 @@
+//! A small expression evaluator shared by conditional breakpoints and the
+//! DAP `evaluate` request. Supports register names,
+//! integer literals and the comparison/arithmetic operators needed to
+//! express breakpoint conditions like `r1 > 10` or `r0 == 0`.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub struct EvalError(pub String);
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Rem,
+}
+
+impl Op {
+    fn from_token(token: &str) -> Option<Self> {
+        Some(match token {
+            "+" => Op::Add,
+            "-" => Op::Sub,
+            "*" => Op::Mul,
+            "==" => Op::Eq,
+            "!=" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            "%" => Op::Rem,
+            _ => return None,
+        })
+    }
+
+    fn apply(self, lhs: i64, rhs: i64) -> i64 {
+        match self {
+            Op::Add => lhs.wrapping_add(rhs),
+            Op::Sub => lhs.wrapping_sub(rhs),
+            Op::Mul => lhs.wrapping_mul(rhs),
+            Op::Rem => {
+                if rhs == 0 {
+                    0
+                } else {
+                    lhs % rhs
+                }
+            }
+            Op::Eq => (lhs == rhs) as i64,
+            Op::Ne => (lhs != rhs) as i64,
+            Op::Lt => (lhs < rhs) as i64,
+            Op::Le => (lhs <= rhs) as i64,
+            Op::Gt => (lhs > rhs) as i64,
+            Op::Ge => (lhs >= rhs) as i64,
+        }
+    }
+}
+
+/// Evaluates a condition expression of the form `<lhs> <op> <rhs>` (e.g.
+/// `r3 == 5`, `r0 >= 10`) against the given register file, returning
+/// whether the breakpoint should actually stop execution.
+///
+/// Register names are resolved via `resolve`, which maps a name like `r3`
+/// to its current value; unrecognized names are treated as integer
+/// literals and parsed directly.
+pub fn eval_condition(expr: &str, resolve: impl Fn(&str) -> Option<i64>) -> Result<bool, EvalError> {
+    let tokens: Vec<&str> = expr.split_whitespace().collect();
+    let [lhs, op, rhs] = tokens.as_slice() else {
+        return Err(EvalError(format!(
+            "expected `<lhs> <op> <rhs>`, got `{expr}`"
+        )));
+    };
+    let op = Op::from_token(op).ok_or_else(|| EvalError(format!("unknown operator `{op}`")))?;
+    let lhs = resolve_operand(lhs, &resolve)?;
+    let rhs = resolve_operand(rhs, &resolve)?;
+    Ok(op.apply(lhs, rhs) != 0)
+}
+
+fn resolve_operand(
+    token: &str,
+    resolve: &impl Fn(&str) -> Option<i64>,
+) -> Result<i64, EvalError> {
+    if let Some(value) = resolve(token) {
+        return Ok(value);
+    }
+    token
+        .parse::<i64>()
+        .map_err(|_| EvalError(format!("unknown identifier or literal `{token}`")))
+}
+
+/// Evaluates an arbitrary debug-console expression: either a single
+/// identifier/literal (e.g. `r3`, `pc`, `42`) or a binary expression of the
+/// form `<lhs> <op> <rhs>`. Used by the `evaluate` request so watch/hover
+/// expressions and conditional breakpoints share one engine.
+pub fn eval_value(expr: &str, resolve: impl Fn(&str) -> Option<i64>) -> Result<i64, EvalError> {
+    let tokens: Vec<&str> = expr.split_whitespace().collect();
+    match tokens.as_slice() {
+        [single] => resolve_operand(single, &resolve),
+        [lhs, op, rhs] => {
+            let op =
+                Op::from_token(op).ok_or_else(|| EvalError(format!("unknown operator `{op}`")))?;
+            let lhs = resolve_operand(lhs, &resolve)?;
+            let rhs = resolve_operand(rhs, &resolve)?;
+            Ok(op.apply(lhs, rhs))
+        }
+        _ => Err(EvalError(format!(
+            "expected an identifier or `<lhs> <op> <rhs>`, got `{expr}`"
+        ))),
+    }
+}
+
+/// Specification for a `hitCondition`: stop only every Nth hit, once the
+/// hit count reaches N, or once it satisfies a comparison against N.
+#[derive(Debug, Clone, Copy)]
+pub enum HitCondition {
+    Equals(u64),
+    AtLeast(u64),
+    Every(u64),
+}
+
+impl HitCondition {
+    pub fn parse(expr: &str) -> Result<Self, EvalError> {
+        let expr = expr.trim();
+        if let Some(rest) = expr.strip_prefix('%') {
+            return Ok(HitCondition::Every(parse_count(rest)?));
+        }
+        if let Some(rest) = expr.strip_prefix(">=") {
+            return Ok(HitCondition::AtLeast(parse_count(rest)?));
+        }
+        if let Some(rest) = expr.strip_prefix("==") {
+            return Ok(HitCondition::Equals(parse_count(rest)?));
+        }
+        // A bare number means "stop once the hit count reaches N".
+        Ok(HitCondition::Equals(parse_count(expr)?))
+    }
+
+    /// Given the 1-based hit count for this breakpoint, returns whether it
+    /// should actually stop execution this time.
+    pub fn should_stop(&self, hit_count: u64) -> bool {
+        match self {
+            HitCondition::Equals(n) => hit_count == *n,
+            HitCondition::AtLeast(n) => hit_count >= *n,
+            HitCondition::Every(n) => *n != 0 && hit_count % n == 0,
+        }
+    }
+}
+
+fn parse_count(s: &str) -> Result<u64, EvalError> {
+    s.trim()
+        .parse::<u64>()
+        .map_err(|_| EvalError(format!("expected an integer count, got `{s}`")))
+}
@@ -0,0 +1,7 @@
+//! Library support for the `forc debug` plugin: a Debug Adapter Protocol
+//! (DAP) server for stepping through Sway programs running on the FuelVM.
+
+mod build;
+pub mod eval;
+pub mod server;
+pub mod vm;
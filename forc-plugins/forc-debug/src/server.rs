@@ -0,0 +1,747 @@
+//! The `forc-debug` Debug Adapter Protocol (DAP) server.
+//!
+//! `DapServer` speaks a line-delimited subset of DAP over whatever
+//! `Read`/`Write` pair it is constructed with, so the same command
+//! dispatch can be driven over stdio (the default, for editors that spawn
+//! the adapter as a child process) or any other transport.
+
+use crate::eval::{eval_condition, eval_value, HitCondition};
+use crate::vm::{resolve_register, DebugVm, Instruction, RunState};
+use dap::events::Event;
+use dap::requests::{
+    Command, EvaluateArguments, LaunchRequestArguments, Request, SetBreakpointsArguments,
+    SetFunctionBreakpointsArguments,
+};
+use dap::responses::{
+    EvaluateResponse, ExceptionInfoResponse, Response, ResponseBody, SetFunctionBreakpointsResponse,
+};
+use dap::types::{
+    BreakpointEventBody, BreakpointEventReason, Capabilities, Scope, SourceBreakpoint,
+    StoppedEventBody, StoppedEventReason, Thread, Variable,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+/// `variablesReference` used for the "Registers" scope.
+pub const REGISTERS_VARIABLE_REF: i64 = 1;
+/// `variablesReference` used for the "VM Instructions" scope.
+pub const INSTRUCTIONS_VARIABLE_REF: i64 = 2;
+
+/// Key under which function breakpoints are stored in `DapServer::breakpoints`,
+/// alongside the real source paths used for line breakpoints.
+const FUNCTION_BREAKPOINTS_KEY: &str = "$functions";
+
+/// Extra data forc-debug expects alongside the standard `launch` request
+/// arguments: the path to the Sway program to build and run.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AdditionalData {
+    pub program: String,
+}
+
+#[derive(Debug)]
+pub struct DapServerError(pub String);
+
+impl fmt::Display for DapServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for DapServerError {}
+
+pub type DapResult = Result<ResponseBody, DapServerError>;
+
+/// A breakpoint set against a source line, tracked alongside the VM
+/// program counter it resolves to once the program has been built.
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    pub id: i64,
+    pub line: i64,
+    pub pc: u64,
+    pub verified: bool,
+    /// Optional `condition` expression (e.g. `r1 == 0`); the breakpoint
+    /// only stops execution when this evaluates true.
+    pub condition: Option<String>,
+    /// Optional `hitCondition` spec (`== N`, `>= N`, `% N`); gates the stop
+    /// on the breakpoint's hit counter instead of (or alongside) `condition`.
+    pub hit_condition: Option<HitCondition>,
+    /// Number of times this breakpoint's instruction has been reached,
+    /// persisted across `Continue` calls.
+    pub hits: u64,
+    /// Set when `condition` or `hitCondition` failed to parse or evaluate;
+    /// surfaced to the editor instead of crashing the session.
+    pub warning: Option<String>,
+    /// Set for function breakpoints (`SetFunctionBreakpoints`); resolved
+    /// against the program's debug symbols instead of a source line.
+    pub function_name: Option<String>,
+}
+
+/// The DAP server for `forc debug`. Holds the connection to the editor and
+/// all state accumulated over the life of a debug session: breakpoints,
+/// the program being debugged, and (once launched) the running VM.
+pub struct DapServer {
+    output: Box<dyn Write + Send>,
+    input: Box<dyn Read + Send>,
+    next_breakpoint_id: i64,
+    breakpoints: HashMap<String, Vec<Breakpoint>>,
+    program_path: Option<String>,
+    vm: Option<DebugVm>,
+    /// The most recent revert/panic, kept around so `ExceptionInfo` can
+    /// describe it after the `Stopped(Exception)` event that reported it.
+    last_exception: Option<ExceptionInfo>,
+    /// Maps a Sway function name to its entry instruction's program
+    /// counter, populated once the program is built.
+    function_symbols: HashMap<String, u64>,
+}
+
+/// Details of a revert/panic captured when the VM stops with
+/// `StoppedEventReason::Exception`, surfaced on demand via
+/// `Command::ExceptionInfo`.
+#[derive(Debug, Clone)]
+struct ExceptionInfo {
+    reason_code: u64,
+    pc: u64,
+}
+
+impl DapServer {
+    /// Builds a server over an arbitrary byte stream. Used directly for the
+    /// stdio transport (the default, for editors that spawn the adapter as
+    /// a child process); `serve_tcp` builds one the same way over a socket.
+    pub fn new(input: Box<dyn Read + Send>, output: Box<dyn Write + Send>) -> Self {
+        Self {
+            output,
+            input,
+            next_breakpoint_id: 0,
+            breakpoints: HashMap::new(),
+            program_path: None,
+            vm: None,
+            last_exception: None,
+            function_symbols: HashMap::new(),
+        }
+    }
+
+    /// Listens on `addr`, accepts a single editor connection, and runs the
+    /// same command-dispatch loop used over stdio. This lets the adapter be
+    /// launched once and attached to from any editor or across a container
+    /// boundary, instead of requiring the editor to spawn it as a child.
+    pub fn serve_tcp(addr: impl ToSocketAddrs) -> Result<i32, DapServerError> {
+        let listener = TcpListener::bind(addr).map_err(|e| DapServerError(e.to_string()))?;
+        let (stream, _) = listener.accept().map_err(|e| DapServerError(e.to_string()))?;
+        let input: Box<dyn Read + Send> = Box::new(stream.try_clone().map_err(|e| DapServerError(e.to_string()))?);
+        let output: Box<dyn Write + Send> = Box::new(stream as TcpStream);
+        DapServer::new(input, output).run()
+    }
+
+    /// Reads requests one per line until the stream closes, dispatching each
+    /// through `handle_command` and writing back its response. Shared by
+    /// every transport so stdio and TCP behave identically. This loop does
+    /// not call `handle_launch`: a `launch` request only records the
+    /// program path, the program is not started, and the loop returns `0`
+    /// once the stream closes.
+    pub fn run(&mut self) -> Result<i32, DapServerError> {
+        let input = std::mem::replace(&mut self.input, Box::new(std::io::empty()));
+        let mut reader = BufReader::new(input);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let bytes_read = reader
+                .read_line(&mut line)
+                .map_err(|e| DapServerError(e.to_string()))?;
+            if bytes_read == 0 {
+                return Ok(0);
+            }
+            let request: Request = serde_json::from_str(line.trim())
+                .map_err(|e| DapServerError(format!("invalid request: {e}")))?;
+            let (result, exit_code) = self.handle_command(request.command);
+            let response = Response {
+                request_seq: request.seq,
+                success: result.is_ok(),
+                body: result.ok(),
+                ..Default::default()
+            };
+            if let Ok(json) = serde_json::to_string(&response) {
+                let _ = writeln!(self.output, "{json}");
+            }
+            if let Some(code) = exit_code {
+                return Ok(code);
+            }
+        }
+    }
+
+    fn send_event(&mut self, event: Event) {
+        if let Ok(json) = serde_json::to_string(&event) {
+            let _ = writeln!(self.output, "{json}");
+        }
+    }
+
+    /// Dispatches a single DAP request, returning the response body to send
+    /// back to the client and, if the session should terminate, the exit
+    /// code of the debuggee.
+    pub fn handle_command(&mut self, command: Command) -> (DapResult, Option<i32>) {
+        match command {
+            Command::Initialize(_) => (Ok(ResponseBody::Initialize(self.capabilities())), None),
+            Command::Launch(args) => (self.handle_launch_request(args), None),
+            Command::SetBreakpoints(args) => (self.handle_set_breakpoints(args), None),
+            Command::ConfigurationDone => (Ok(ResponseBody::ConfigurationDone), None),
+            Command::Threads => (Ok(ResponseBody::Threads(dap::responses::ThreadsResponse {
+                threads: vec![Thread {
+                    id: 1,
+                    name: "main".into(),
+                }],
+            })), None),
+            Command::StackTrace(_) => (
+                Ok(ResponseBody::StackTrace(
+                    dap::responses::StackTraceResponse {
+                        stack_frames: self.stack_frames(),
+                        total_frames: None,
+                    },
+                )),
+                None,
+            ),
+            Command::Scopes(_) => (Ok(ResponseBody::Scopes(dap::responses::ScopesResponse {
+                scopes: vec![
+                    Scope {
+                        name: "Registers".into(),
+                        variables_reference: REGISTERS_VARIABLE_REF,
+                        ..Default::default()
+                    },
+                    Scope {
+                        name: "VM Instructions".into(),
+                        variables_reference: INSTRUCTIONS_VARIABLE_REF,
+                        ..Default::default()
+                    },
+                ],
+            })), None),
+            Command::Variables(args) => (self.handle_variables(args.variables_reference), None),
+            Command::Continue(_) => self.resume(),
+            Command::Next(_) => self.step_over(),
+            Command::StepIn(_) => self.step_in(),
+            Command::StepOut(_) => self.step_out(),
+            Command::Evaluate(args) => (self.handle_evaluate(args), None),
+            Command::ExceptionInfo(_) => (self.handle_exception_info(), None),
+            Command::SetFunctionBreakpoints(args) => {
+                (self.handle_set_function_breakpoints(args), None)
+            }
+            other => (
+                Err(DapServerError(format!("unsupported command: {other:?}"))),
+                None,
+            ),
+        }
+    }
+
+    /// DAP capabilities advertised to the client in response to
+    /// `initialize`. Reflects exactly what this server implements so
+    /// editors can gate their UI (breakpoint dialogs, stepping controls,
+    /// etc.) accordingly instead of guessing.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_configuration_done_request: Some(true),
+            supports_conditional_breakpoints: Some(true),
+            supports_function_breakpoints: Some(true),
+            supports_exception_info_request: Some(true),
+            support_terminate_debuggee: Some(true),
+            supports_delayed_stack_trace_loading: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn handle_launch_request(&mut self, args: LaunchRequestArguments) -> DapResult {
+        let additional_data = args
+            .additional_data
+            .ok_or_else(|| DapServerError("missing launch arguments".into()))?;
+        let data: AdditionalData = serde_json::from_value(additional_data)
+            .map_err(|e| DapServerError(format!("invalid launch arguments: {e}")))?;
+        self.program_path = Some(data.program);
+        Ok(ResponseBody::Launch)
+    }
+
+    fn handle_set_breakpoints(&mut self, args: SetBreakpointsArguments) -> DapResult {
+        let path = args.source.path.unwrap_or_default();
+        let mut resolved = Vec::new();
+        let mut entries: Vec<Breakpoint> = args
+            .breakpoints
+            .unwrap_or_default()
+            .into_iter()
+            .map(|bp: SourceBreakpoint| {
+                let id = self.next_breakpoint_id;
+                self.next_breakpoint_id += 1;
+                let (hit_condition, warning) = match bp.hit_condition.as_deref().map(HitCondition::parse) {
+                    Some(Ok(hc)) => (Some(hc), None),
+                    Some(Err(e)) => (None, Some(format!("invalid hitCondition: {e}"))),
+                    None => (None, None),
+                };
+                // The real program counter is only known once the program
+                // has been built; resolution happens in `handle_launch`.
+                Breakpoint {
+                    id,
+                    line: bp.line,
+                    pc: 0,
+                    verified: false,
+                    condition: bp.condition,
+                    hit_condition,
+                    hits: 0,
+                    warning,
+                    function_name: None,
+                }
+            })
+            .collect();
+        // The program may already be running (breakpoints can be added
+        // mid-session), in which case resolve against it immediately
+        // instead of waiting for a `launch` that already happened.
+        if let Some(vm) = self.vm.as_ref() {
+            Self::resolve_entries(&vm.instructions, &self.function_symbols, &mut entries);
+        }
+        for bp in &entries {
+            resolved.push(dap::types::Breakpoint {
+                id: Some(bp.id),
+                verified: bp.verified,
+                line: Some(bp.line),
+                message: bp.warning.clone(),
+                ..Default::default()
+            });
+        }
+        self.breakpoints.insert(path, entries);
+        Ok(ResponseBody::SetBreakpoints(
+            dap::responses::SetBreakpointsResponse {
+                breakpoints: resolved,
+            },
+        ))
+    }
+
+    /// Breaks on a Sway function by name regardless of where it's defined,
+    /// resolving each name against the program's debug symbols once it has
+    /// been built (see `resolve_breakpoints`). Especially useful for
+    /// contract ABI methods, where the user knows the function but not the
+    /// line it lives on.
+    fn handle_set_function_breakpoints(&mut self, args: SetFunctionBreakpointsArguments) -> DapResult {
+        let mut resolved = Vec::new();
+        let mut entries: Vec<Breakpoint> = args
+            .breakpoints
+            .into_iter()
+            .map(|fb| {
+                let id = self.next_breakpoint_id;
+                self.next_breakpoint_id += 1;
+                let (hit_condition, warning) =
+                    match fb.hit_condition.as_deref().map(HitCondition::parse) {
+                        Some(Ok(hc)) => (Some(hc), None),
+                        Some(Err(e)) => (None, Some(format!("invalid hitCondition: {e}"))),
+                        None => (None, None),
+                    };
+                Breakpoint {
+                    id,
+                    line: 0,
+                    pc: 0,
+                    verified: false,
+                    condition: fb.condition,
+                    hit_condition,
+                    hits: 0,
+                    warning,
+                    function_name: Some(fb.name),
+                }
+            })
+            .collect();
+        if let Some(vm) = self.vm.as_ref() {
+            Self::resolve_entries(&vm.instructions, &self.function_symbols, &mut entries);
+        }
+        for bp in &entries {
+            resolved.push(dap::types::Breakpoint {
+                id: Some(bp.id),
+                verified: bp.verified,
+                message: bp.warning.clone(),
+                ..Default::default()
+            });
+        }
+        self.breakpoints
+            .insert(FUNCTION_BREAKPOINTS_KEY.to_string(), entries);
+        Ok(ResponseBody::SetFunctionBreakpoints(
+            SetFunctionBreakpointsResponse {
+                breakpoints: resolved,
+            },
+        ))
+    }
+
+    /// Builds and loads the program named by the `launch` request, resolves
+    /// breakpoints against its compiled debug info, and runs until the
+    /// first breakpoint (or program exit). Returns `true` if the session is
+    /// still running (i.e. stopped at a breakpoint or a revert) and `false`
+    /// if the program ran to completion without hitting either.
+    pub fn handle_launch(&mut self) -> Result<bool, DapServerError> {
+        let program_path = self
+            .program_path
+            .clone()
+            .ok_or_else(|| DapServerError("launch called before program was set".into()))?;
+        let (instructions, symbols) = self.build_program(&program_path)?;
+        self.function_symbols = symbols;
+        self.resolve_breakpoints(&instructions);
+        self.vm = Some(DebugVm::new(instructions));
+        Ok(self.run_until_stop().is_none())
+    }
+
+    /// Builds the Sway program at `path`: reads the source and lowers it
+    /// into the instruction stream the debug VM steps through, plus the
+    /// function-name -> entry-pc table breakpoints resolve function names
+    /// against. See `crate::build` for how that lowering works — there is
+    /// no `forc pkg`/FuelVM bytecode available here, so it is a direct,
+    /// simplified lowering rather than a real compile.
+    fn build_program(
+        &self,
+        path: &str,
+    ) -> Result<(Vec<Instruction>, HashMap<String, u64>), DapServerError> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| DapServerError(format!("failed to read `{path}`: {e}")))?;
+        Ok(crate::build::build_program(&source))
+    }
+
+    fn resolve_breakpoints(&mut self, instructions: &[Instruction]) {
+        let function_symbols = &self.function_symbols;
+        for bps in self.breakpoints.values_mut() {
+            Self::resolve_entries(instructions, function_symbols, bps);
+        }
+    }
+
+    /// Resolves each breakpoint's `pc` (and `verified` flag) against the
+    /// built program: by source line for ordinary breakpoints, or by
+    /// looking up `function_name` in the debug symbols for function
+    /// breakpoints. Shared by the initial resolution done at `launch` and
+    /// by breakpoints added later, once a program is already running.
+    fn resolve_entries(
+        instructions: &[Instruction],
+        function_symbols: &HashMap<String, u64>,
+        entries: &mut [Breakpoint],
+    ) {
+        for bp in entries.iter_mut() {
+            let instr = match &bp.function_name {
+                Some(name) => function_symbols
+                    .get(name)
+                    .and_then(|pc| instructions.iter().find(|i| i.pc == *pc)),
+                None => instructions.iter().find(|i| i.source_line == Some(bp.line)),
+            };
+            if let Some(instr) = instr {
+                bp.pc = instr.pc;
+                bp.verified = true;
+            } else if bp.function_name.is_some() {
+                bp.warning.get_or_insert_with(|| {
+                    format!(
+                        "function `{}` not found in debug symbols",
+                        bp.function_name.as_deref().unwrap_or_default()
+                    )
+                });
+            }
+        }
+    }
+
+    /// Runs the VM forward until it stops for some reason worth reporting,
+    /// returning `None` if it's sitting at a breakpoint or exception (i.e.
+    /// the session is still alive) or `Some(exit_code)` once the program
+    /// has run to completion.
+    fn run_until_stop(&mut self) -> Option<i32> {
+        loop {
+            let pc = self.vm.as_ref()?.pc;
+            if let Some(id) = self.check_breakpoint_at(pc) {
+                self.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Breakpoint,
+                    hit_breakpoint_ids: Some(vec![id]),
+                    thread_id: Some(1),
+                    ..Default::default()
+                }));
+                return None;
+            }
+            if let Some(state) = self.vm.as_mut().expect("vm present").step() {
+                return self.handle_run_state(state);
+            }
+        }
+    }
+
+    /// Records and reports a terminal VM state reached mid-step: a revert
+    /// stops the session (with `Stopped(Exception)`, details retrievable
+    /// via `ExceptionInfo`) without exiting; completion exits with the
+    /// program's return code.
+    fn handle_run_state(&mut self, state: RunState) -> Option<i32> {
+        match state {
+            RunState::Completed { return_code } => Some(return_code as i32),
+            RunState::Reverted { reason_code, pc } => {
+                self.last_exception = Some(ExceptionInfo { reason_code, pc });
+                self.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Exception,
+                    hit_breakpoint_ids: None,
+                    thread_id: Some(1),
+                    ..Default::default()
+                }));
+                None
+            }
+        }
+    }
+
+    /// Checks whether the breakpoint (if any) armed at `pc` should actually
+    /// stop execution, taking its `condition` and `hitCondition` into
+    /// account. Bumps the hit counter regardless of whether the breakpoint
+    /// ends up stopping, so counters persist correctly across `Continue`.
+    fn check_breakpoint_at(&mut self, pc: u64) -> Option<i64> {
+        let registers = self.vm.as_ref()?.registers;
+        let mut stop_id = None;
+        let mut condition_error = None;
+        for bp in self.breakpoints.values_mut().flatten() {
+            if !bp.verified || bp.pc != pc {
+                continue;
+            }
+            bp.hits += 1;
+            if let Some(hit_condition) = &bp.hit_condition {
+                if !hit_condition.should_stop(bp.hits) {
+                    continue;
+                }
+            }
+            if let Some(condition) = &bp.condition {
+                match eval_condition(condition, |name| resolve_register(&registers, name)) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        // A malformed condition shouldn't crash the
+                        // session; surface it and fall back to stopping,
+                        // same as an unconditional breakpoint.
+                        let warning = format!("breakpoint condition error: {e}");
+                        bp.warning = Some(warning.clone());
+                        condition_error = Some((bp.id, bp.line, warning));
+                    }
+                }
+            }
+            stop_id = Some(bp.id);
+            break;
+        }
+        // Emitted after the loop so we're not holding a mutable borrow of
+        // `self.breakpoints` while calling `self.send_event`.
+        if let Some((id, line, warning)) = condition_error {
+            self.send_event(Event::Breakpoint(BreakpointEventBody {
+                reason: BreakpointEventReason::Changed,
+                breakpoint: dap::types::Breakpoint {
+                    id: Some(id),
+                    verified: true,
+                    line: Some(line),
+                    message: Some(warning),
+                    ..Default::default()
+                },
+            }));
+        }
+        stop_id
+    }
+
+    fn resume(&mut self) -> (DapResult, Option<i32>) {
+        let Some(vm) = self.vm.as_mut() else {
+            return (
+                Err(DapServerError("continue called before launch".into())),
+                None,
+            );
+        };
+        // Step past the breakpoint we're currently sitting on so we don't
+        // immediately re-trigger it.
+        if let Some(state) = vm.step() {
+            return (Ok(ResponseBody::Continue), self.handle_run_state(state));
+        }
+        (Ok(ResponseBody::Continue), self.run_until_stop())
+    }
+
+    fn step_over(&mut self) -> (DapResult, Option<i32>) {
+        let Some(vm) = self.vm.as_mut() else {
+            return (
+                Err(DapServerError("next called before launch".into())),
+                None,
+            );
+        };
+        if let Some(state) = vm.step() {
+            return (Ok(ResponseBody::Next), self.handle_run_state(state));
+        }
+        self.send_event(Event::Stopped(StoppedEventBody {
+            reason: StoppedEventReason::Step,
+            hit_breakpoint_ids: None,
+            thread_id: Some(1),
+            ..Default::default()
+        }));
+        (Ok(ResponseBody::Next), None)
+    }
+
+    /// Steps until entering a called function frame, i.e. the call depth
+    /// increases relative to where stepping started. Breakpoints hit along
+    /// the way still take priority over the step target.
+    fn step_in(&mut self) -> (DapResult, Option<i32>) {
+        let exit_code = self.step_while(|start_depth, vm| vm.call_depth > start_depth);
+        (Ok(ResponseBody::StepIn), exit_code)
+    }
+
+    /// Steps until the current frame returns, i.e. the call depth
+    /// decreases relative to where stepping started. Breakpoints hit along
+    /// the way still take priority over the step target.
+    fn step_out(&mut self) -> (DapResult, Option<i32>) {
+        let exit_code = self.step_while(|start_depth, vm| vm.call_depth < start_depth);
+        (Ok(ResponseBody::StepOut), exit_code)
+    }
+
+    /// Drives the VM one instruction at a time until either a breakpoint is
+    /// hit, `target` reports the requested call-depth change, or the
+    /// program runs to completion. Returns the debuggee's exit code if the
+    /// program completed, or `None` if the session is still stopped.
+    fn step_while(&mut self, target: impl Fn(u64, &DebugVm) -> bool) -> Option<i32> {
+        let start_depth = self.vm.as_ref()?.call_depth;
+        loop {
+            if let Some(state) = self.vm.as_mut().expect("vm present").step() {
+                return self.handle_run_state(state);
+            }
+            let pc = self.vm.as_ref().expect("vm present").pc;
+            if let Some(id) = self.check_breakpoint_at(pc) {
+                self.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Breakpoint,
+                    hit_breakpoint_ids: Some(vec![id]),
+                    thread_id: Some(1),
+                    ..Default::default()
+                }));
+                return None;
+            }
+            if target(start_depth, self.vm.as_ref().expect("vm present")) {
+                self.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Step,
+                    hit_breakpoint_ids: None,
+                    thread_id: Some(1),
+                    ..Default::default()
+                }));
+                return None;
+            }
+        }
+    }
+
+    /// Evaluates a debug console / watch / hover expression against live VM
+    /// state: registers, the program counter, the current opcode, and
+    /// `mem[N]`/`stack[N]` indexing, using the same expression engine as
+    /// conditional breakpoints. `context` ("watch", "repl", "hover") only
+    /// affects formatting: watch and hover expressions read better in hex,
+    /// the REPL defaults to decimal.
+    fn handle_evaluate(&self, args: EvaluateArguments) -> DapResult {
+        let vm = self
+            .vm
+            .as_ref()
+            .ok_or_else(|| DapServerError("evaluate called before launch".into()))?;
+        let expression = args.expression.trim();
+        let hex = matches!(args.context.as_deref(), Some("watch") | Some("hover"));
+
+        let result = if expression == "opcode" {
+            vm.current_instruction()
+                .map(|i| i.opcode.clone())
+                .unwrap_or_else(|| "<unknown>".into())
+        } else {
+            let value = eval_value(expression, |token| Self::resolve_evaluate_token(vm, token))
+                .map_err(|e| DapServerError(e.to_string()))?;
+            if hex {
+                format!("{value:#x}")
+            } else {
+                format!("{value}")
+            }
+        };
+
+        Ok(ResponseBody::Evaluate(EvaluateResponse {
+            result,
+            variables_reference: 0,
+            ..Default::default()
+        }))
+    }
+
+    /// Returns the revert code, a human-readable description, and the
+    /// source location of the most recent exception stop. Editors call
+    /// this right after a `Stopped(Exception)` event so they can pop up
+    /// the failure reason instead of leaving the session looking like it
+    /// just exited with a bare nonzero code.
+    fn handle_exception_info(&self) -> DapResult {
+        let exception = self
+            .last_exception
+            .as_ref()
+            .ok_or_else(|| DapServerError("no exception to report".into()))?;
+        let line = self
+            .vm
+            .as_ref()
+            .and_then(|vm| vm.instructions.iter().find(|i| i.pc == exception.pc))
+            .and_then(|i| i.source_line);
+        let location = line
+            .map(|l| format!(" (line {l})"))
+            .unwrap_or_else(|| format!(" (pc {:#06x})", exception.pc));
+        Ok(ResponseBody::ExceptionInfo(ExceptionInfoResponse {
+            exception_id: format!("revert:{}", exception.reason_code),
+            description: Some(format!(
+                "Sway program reverted with code {}{location}",
+                exception.reason_code
+            )),
+            ..Default::default()
+        }))
+    }
+
+    fn resolve_evaluate_token(vm: &DebugVm, token: &str) -> Option<i64> {
+        if let Some(value) = resolve_register(&vm.registers, token) {
+            return Some(value);
+        }
+        if token == "pc" {
+            return Some(vm.pc as i64);
+        }
+        if let Some(index) = token.strip_prefix("mem[").and_then(|s| s.strip_suffix(']')) {
+            return vm.memory.get(index.parse::<usize>().ok()?).map(|v| *v as i64);
+        }
+        if let Some(index) = token.strip_prefix("stack[").and_then(|s| s.strip_suffix(']')) {
+            return vm.stack.get(index.parse::<usize>().ok()?).map(|v| *v as i64);
+        }
+        None
+    }
+
+    fn stack_frames(&self) -> Vec<dap::types::StackFrame> {
+        let Some(vm) = self.vm.as_ref() else {
+            return Vec::new();
+        };
+        vec![dap::types::StackFrame {
+            id: 0,
+            name: "main".into(),
+            line: vm
+                .current_instruction()
+                .and_then(|i| i.source_line)
+                .unwrap_or(0),
+            column: 0,
+            ..Default::default()
+        }]
+    }
+
+    fn handle_variables(&self, variables_reference: i64) -> DapResult {
+        let variables = match variables_reference {
+            REGISTERS_VARIABLE_REF => self.register_variables(),
+            INSTRUCTIONS_VARIABLE_REF => self.instruction_variables(),
+            other => {
+                return Err(DapServerError(format!(
+                    "unknown variablesReference: {other}"
+                )))
+            }
+        };
+        Ok(ResponseBody::Variables(dap::responses::VariablesResponse {
+            variables,
+        }))
+    }
+
+    fn register_variables(&self) -> Vec<Variable> {
+        let registers = self.vm.as_ref().map(|vm| vm.registers).unwrap_or_default();
+        (0..registers.len())
+            .map(|i| Variable {
+                name: format!("r{i}"),
+                value: format!("{}", registers.get(i).copied().unwrap_or(0)),
+                variables_reference: 0,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn instruction_variables(&self) -> Vec<Variable> {
+        let Some(vm) = self.vm.as_ref() else {
+            return Vec::new();
+        };
+        vm.instructions
+            .iter()
+            .map(|i| Variable {
+                name: format!("{:#06x}", i.pc),
+                value: i.opcode.clone(),
+                variables_reference: 0,
+                ..Default::default()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,106 @@
+//! A minimal stand-in for the FuelVM interpreter, used by the DAP server to
+//! drive execution one instruction at a time and inspect state between
+//! steps. It steps the synthetic instruction stream produced by
+//! `crate::build` rather than real FuelVM bytecode.
+
+/// Number of general-purpose + reserved registers exposed by the FuelVM.
+pub const REGISTER_COUNT: usize = 64;
+
+/// A single decoded instruction, paired with the source line it was
+/// generated from (when debug info is available).
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub pc: u64,
+    pub opcode: String,
+    pub raw: u64,
+    pub source_line: Option<i64>,
+}
+
+/// Terminal outcome of a single `DebugVm::step` call: either the program
+/// finished normally, or it reverted/panicked. `None` means execution is
+/// still going and the caller should keep stepping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunState {
+    /// The program panicked or a `RVRT` instruction was executed.
+    Reverted { reason_code: u64, pc: u64 },
+    /// The program ran to completion.
+    Completed { return_code: i64 },
+}
+
+/// The VM state the DAP server needs: register file, program counter,
+/// instruction stream and call-stack depth.
+pub struct DebugVm {
+    pub registers: [u64; REGISTER_COUNT],
+    pub pc: u64,
+    pub instructions: Vec<Instruction>,
+    /// Depth of the call stack; incremented on `CALL`, decremented on
+    /// `RET`/`RETD`. Used to detect function entry/exit for stepIn/stepOut.
+    pub call_depth: u64,
+    /// VM heap/memory, addressable by the debug console (`mem[N]`).
+    pub memory: Vec<u64>,
+    /// VM data stack, addressable by the debug console (`stack[N]`).
+    pub stack: Vec<u64>,
+}
+
+/// Resolves a register name (`r0`..`r63`) to its current value for use by
+/// the expression evaluator. Anything else is not a register and is left
+/// for the caller to interpret as a literal.
+pub fn resolve_register(registers: &[u64; REGISTER_COUNT], name: &str) -> Option<i64> {
+    let index: usize = name.strip_prefix('r')?.parse().ok()?;
+    registers.get(index).map(|v| *v as i64)
+}
+
+impl DebugVm {
+    /// Number of zeroed `memory`/`stack` slots a freshly booted VM starts
+    /// with, so `mem[N]`/`stack[N]` evaluate expressions have something to
+    /// resolve against before the debuggee has written anything.
+    const INITIAL_ADDRESSABLE_SLOTS: usize = 16;
+
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Self {
+            registers: [0; REGISTER_COUNT],
+            pc: 0,
+            instructions,
+            call_depth: 0,
+            memory: vec![0; Self::INITIAL_ADDRESSABLE_SLOTS],
+            stack: vec![0; Self::INITIAL_ADDRESSABLE_SLOTS],
+        }
+    }
+
+    pub fn current_instruction(&self) -> Option<&Instruction> {
+        self.instructions.iter().find(|i| i.pc == self.pc)
+    }
+
+    /// Executes a single instruction, advancing `pc` and updating
+    /// `call_depth`. Returns `Some(RunState)` if that instruction ended the
+    /// program (normally or via revert/panic), or `None` if it's still
+    /// running.
+    pub fn step(&mut self) -> Option<RunState> {
+        let Some(instruction) = self.current_instruction().cloned() else {
+            return Some(RunState::Completed { return_code: 0 });
+        };
+        match instruction.opcode.as_str() {
+            "CALL" => self.call_depth += 1,
+            "RET" | "RETD" => self.call_depth = self.call_depth.saturating_sub(1),
+            "RVRT" => {
+                // This mock VM never decodes real FuelVM operands — there
+                // is no bytecode to decode in the first place; see
+                // `crate::build`'s doc comment. As a fixed simplification
+                // it always reads the revert reason from register 1,
+                // regardless of which register a real RVRT would encode.
+                let reason_code = self.registers[1];
+                return Some(RunState::Reverted {
+                    reason_code,
+                    pc: self.pc,
+                });
+            }
+            _ => {}
+        }
+        self.pc += 1;
+        if self.pc as usize >= self.instructions.len() {
+            Some(RunState::Completed { return_code: 0 })
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use forc_debug::eval::{eval_condition, eval_value, HitCondition};
+
+fn regs(values: &[(&str, i64)]) -> impl Fn(&str) -> Option<i64> + '_ {
+    move |name| values.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+}
+
+#[test]
+fn evaluates_register_comparisons() {
+    let resolve = regs(&[("r1", 5)]);
+    assert!(eval_condition("r1 == 5", &resolve).unwrap());
+    assert!(!eval_condition("r1 > 5", &resolve).unwrap());
+    assert!(eval_condition("r1 >= 5", &resolve).unwrap());
+}
+
+#[test]
+fn rejects_malformed_expressions() {
+    let resolve = regs(&[]);
+    assert!(eval_condition("r1 ==", &resolve).is_err());
+    assert!(eval_condition("r1 ?? 5", &resolve).is_err());
+}
+
+#[test]
+fn hit_condition_every_nth() {
+    let cond = HitCondition::parse("% 3").unwrap();
+    assert!(!cond.should_stop(1));
+    assert!(!cond.should_stop(2));
+    assert!(cond.should_stop(3));
+    assert!(cond.should_stop(6));
+}
+
+#[test]
+fn eval_value_resolves_single_identifiers_and_literals() {
+    let resolve = regs(&[("r2", 7)]);
+    assert_eq!(eval_value("r2", &resolve).unwrap(), 7);
+    assert_eq!(eval_value("42", &resolve).unwrap(), 42);
+}
+
+#[test]
+fn eval_value_computes_binary_expressions() {
+    let resolve = regs(&[("r2", 7)]);
+    assert_eq!(eval_value("r2 + 3", &resolve).unwrap(), 10);
+    assert_eq!(eval_value("r2 == 7", &resolve).unwrap(), 1);
+}
+
+#[test]
+fn hit_condition_at_least() {
+    let cond = HitCondition::parse(">= 2").unwrap();
+    assert!(!cond.should_stop(1));
+    assert!(cond.should_stop(2));
+    assert!(cond.should_stop(3));
+}
@@ -1,14 +1,25 @@
 use dap::{
     events::Event,
-    requests::{Command, LaunchRequestArguments, SetBreakpointsArguments, VariablesArguments},
-    responses::ResponseBody,
-    types::{Source, SourceBreakpoint, StoppedEventReason},
+    requests::{
+        Command, LaunchRequestArguments, Request, SetBreakpointsArguments,
+        SetFunctionBreakpointsArguments, VariablesArguments,
+    },
+    responses::{Response, ResponseBody},
+    types::{Capabilities, FunctionBreakpoint, Source, SourceBreakpoint, StoppedEventReason},
 };
 use forc_debug::server::{
     AdditionalData, DapServer, INSTRUCTIONS_VARIABLE_REF, REGISTERS_VARIABLE_REF,
 };
 use std::sync::Mutex;
-use std::{env, io::Write, path::PathBuf, sync::Arc};
+use std::{
+    env,
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
 
 pub fn sway_workspace_dir() -> PathBuf {
     env::current_dir().unwrap().parent().unwrap().to_path_buf()
@@ -51,6 +62,437 @@ impl EventCapture {
     }
 }
 
+/// The `initialize` response should advertise exactly the capability set
+/// the Helix DAP client negotiates, so editors can gate breakpoint dialogs
+/// and stepping controls instead of guessing what forc-debug supports.
+#[test]
+fn test_initialize_reports_capabilities() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture);
+    let mut server = DapServer::new(input, output);
+
+    let (result, exit_code) = server.handle_command(Command::Initialize(Default::default()));
+    assert!(exit_code.is_none());
+    match result.expect("initialize result") {
+        ResponseBody::Initialize(capabilities) => {
+            assert_eq!(
+                capabilities,
+                Capabilities {
+                    supports_configuration_done_request: Some(true),
+                    supports_conditional_breakpoints: Some(true),
+                    supports_function_breakpoints: Some(true),
+                    supports_exception_info_request: Some(true),
+                    support_terminate_debuggee: Some(true),
+                    supports_delayed_stack_trace_loading: Some(true),
+                    ..Default::default()
+                }
+            );
+        }
+        other => panic!("Expected Initialize response, got {:?}", other),
+    }
+}
+
+/// `SetBreakpoints` should accept `condition` and `hitCondition` per
+/// breakpoint, and a malformed `hitCondition` should come back as an
+/// unverified breakpoint with an explanatory message rather than an error.
+#[test]
+fn test_set_breakpoints_with_conditions() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture);
+    let mut server = DapServer::new(input, output);
+
+    let (result, exit_code) =
+        server.handle_command(Command::SetBreakpoints(SetBreakpointsArguments {
+            source: Source {
+                path: Some("tests/fixtures/simple/src/main.sw".into()),
+                ..Default::default()
+            },
+            breakpoints: Some(vec![
+                SourceBreakpoint {
+                    line: 21,
+                    condition: Some("r1 == 0".into()),
+                    ..Default::default()
+                },
+                SourceBreakpoint {
+                    line: 30,
+                    hit_condition: Some("% 2".into()),
+                    ..Default::default()
+                },
+                SourceBreakpoint {
+                    line: 39,
+                    hit_condition: Some("not a number".into()),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        }));
+    assert!(exit_code.is_none());
+    match result.expect("set breakpoints result") {
+        ResponseBody::SetBreakpoints(res) => {
+            assert_eq!(res.breakpoints.len(), 3);
+            assert!(res.breakpoints[2].message.is_some());
+        }
+        other => panic!("Expected SetBreakpoints response, got {:?}", other),
+    }
+}
+
+/// A malformed `hitCondition` is caught at *set* time (see
+/// `test_set_breakpoints_with_conditions`), before the program ever runs.
+/// A `condition` is only parsed when it is evaluated against live register
+/// state; one that fails there (a runtime error) should still stop the
+/// session, and should surface an updated breakpoint event carrying the
+/// warning instead of silently dropping it.
+#[test]
+fn test_runtime_malformed_condition_surfaces_warning() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture.clone());
+    let mut server = DapServer::new(input, output);
+
+    let program_path = test_fixtures_dir().join("simple/src/main.sw");
+    let source_str = program_path.to_string_lossy().to_string();
+
+    let additional_data = serde_json::to_value(AdditionalData {
+        program: source_str.clone(),
+    })
+    .unwrap();
+    let _ = server.handle_command(Command::Launch(LaunchRequestArguments {
+        additional_data: Some(additional_data),
+        ..Default::default()
+    }));
+    let _ = server.handle_command(Command::SetBreakpoints(SetBreakpointsArguments {
+        source: Source {
+            path: Some(source_str),
+            ..Default::default()
+        },
+        breakpoints: Some(vec![SourceBreakpoint {
+            line: 21,
+            // Well-formed enough to set, but `eval_condition` requires
+            // exactly `<lhs> <op> <rhs>`, so this fails at evaluation time.
+            condition: Some("not-a-condition".into()),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }));
+
+    let keep_running = server.handle_launch().expect("launched without error");
+    assert!(keep_running, "a malformed condition should still stop, not skip, the breakpoint");
+
+    let warning_event = output_capture.take_event().expect("received warning event");
+    match warning_event {
+        Event::Breakpoint(body) => {
+            assert_eq!(body.breakpoint.id, Some(0));
+            assert!(body.breakpoint.message.is_some());
+        }
+        other => panic!("Expected Breakpoint event, got {:?}", other),
+    }
+    assert_stopped_breakpoint_event(output_capture.take_event(), 0);
+}
+
+/// `serve_tcp` should accept a single socket connection and run the same
+/// command dispatch loop as the stdio transport, so remote editors can
+/// attach to an already-running adapter.
+#[test]
+fn test_tcp_transport_dispatches_commands() {
+    let addr = "127.0.0.1:17890";
+    thread::spawn(move || {
+        let _ = DapServer::serve_tcp(addr);
+    });
+    // Give the listener a moment to come up before connecting.
+    thread::sleep(Duration::from_millis(100));
+
+    let mut stream = TcpStream::connect(addr).expect("connect to forc-debug over tcp");
+    let request = Request {
+        seq: 1,
+        command: Command::Initialize(Default::default()),
+    };
+    let line = serde_json::to_string(&request).unwrap();
+    writeln!(stream, "{line}").unwrap();
+
+    let mut reader = BufReader::new(stream);
+    let mut response_line = String::new();
+    reader.read_line(&mut response_line).unwrap();
+    let response: Response = serde_json::from_str(response_line.trim()).unwrap();
+    assert!(response.success);
+}
+
+/// `Command::Evaluate` should reject expressions before the program has
+/// been launched, rather than panicking against a missing VM.
+#[test]
+fn test_evaluate_before_launch_errors() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture);
+    let mut server = DapServer::new(input, output);
+
+    let (result, exit_code) = server.handle_command(Command::Evaluate(dap::requests::EvaluateArguments {
+        expression: "r0".into(),
+        ..Default::default()
+    }));
+    assert!(exit_code.is_none());
+    assert!(result.is_err());
+}
+
+/// `Command::Evaluate` against a launched, stopped VM should resolve
+/// registers, `pc`, `opcode`, and `mem[N]`/`stack[N]` indexing, formatting
+/// the result in hex for watch/hover contexts and decimal otherwise.
+#[test]
+fn test_evaluate_resolves_vm_state_and_formats_by_context() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture);
+    let mut server = DapServer::new(input, output);
+
+    let program_path = test_fixtures_dir().join("simple/src/main.sw");
+    let source_str = program_path.to_string_lossy().to_string();
+
+    let additional_data = serde_json::to_value(AdditionalData {
+        program: source_str.clone(),
+    })
+    .unwrap();
+    let _ = server.handle_command(Command::Launch(LaunchRequestArguments {
+        additional_data: Some(additional_data),
+        ..Default::default()
+    }));
+    let _ = server.handle_command(Command::SetBreakpoints(SetBreakpointsArguments {
+        source: Source {
+            path: Some(source_str),
+            ..Default::default()
+        },
+        breakpoints: Some(vec![SourceBreakpoint {
+            line: 21,
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }));
+    let keep_running = server.handle_launch().expect("launched without error");
+    assert!(keep_running);
+
+    let evaluate = |server: &mut DapServer, expression: &str, context: Option<&str>| {
+        match server
+            .handle_command(Command::Evaluate(dap::requests::EvaluateArguments {
+                expression: expression.into(),
+                context: context.map(String::from),
+                ..Default::default()
+            }))
+            .0
+            .expect("evaluate result")
+        {
+            ResponseBody::Evaluate(res) => res.result,
+            other => panic!("Expected Evaluate response, got {:?}", other),
+        }
+    };
+
+    assert_eq!(evaluate(&mut server, "pc", None), "0");
+    assert_eq!(evaluate(&mut server, "opcode", None), "MOVE");
+    assert_eq!(evaluate(&mut server, "r0", None), "0");
+    assert_eq!(evaluate(&mut server, "r0", Some("watch")), "0x0");
+    assert_eq!(evaluate(&mut server, "mem[0]", Some("hover")), "0x0");
+    assert_eq!(evaluate(&mut server, "stack[0]", None), "0");
+}
+
+/// `Command::ExceptionInfo` should error out cleanly when queried before
+/// any revert has happened, rather than returning a stale or empty payload.
+#[test]
+fn test_exception_info_before_exception_errors() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture);
+    let mut server = DapServer::new(input, output);
+
+    let (result, exit_code) = server.handle_command(Command::ExceptionInfo(Default::default()));
+    assert!(exit_code.is_none());
+    assert!(result.is_err());
+}
+
+/// Launching a program that reverts should stop with
+/// `StoppedEventReason::Exception` instead of exiting, and `ExceptionInfo`
+/// should describe the revert (reason code and source location).
+#[test]
+fn test_launch_reverting_program_reports_exception() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture.clone());
+    let mut server = DapServer::new(input, output);
+
+    let program_path = test_fixtures_dir().join("reverting/src/main.sw");
+    let additional_data = serde_json::to_value(AdditionalData {
+        program: program_path.to_string_lossy().to_string(),
+    })
+    .unwrap();
+    let (result, exit_code) = server.handle_command(Command::Launch(LaunchRequestArguments {
+        additional_data: Some(additional_data),
+        ..Default::default()
+    }));
+    assert!(result.is_ok());
+    assert!(exit_code.is_none());
+
+    let keep_running = server.handle_launch().expect("launched without error");
+    assert!(keep_running, "a revert should stop, not exit, the session");
+
+    let event = output_capture.take_event().expect("received event");
+    match event {
+        Event::Stopped(body) => {
+            assert!(matches!(body.reason, StoppedEventReason::Exception));
+        }
+        other => panic!("Expected Stopped event, got {:?}", other),
+    }
+
+    let (result, exit_code) = server.handle_command(Command::ExceptionInfo(Default::default()));
+    assert!(exit_code.is_none());
+    match result.expect("exception info result") {
+        ResponseBody::ExceptionInfo(res) => {
+            assert_eq!(res.exception_id, "revert:0");
+            assert_eq!(
+                res.description.as_deref(),
+                Some("Sway program reverted with code 0 (line 5)")
+            );
+        }
+        other => panic!("Expected ExceptionInfo response, got {:?}", other),
+    }
+}
+
+/// Launches the `stepping` fixture (`main` calls `helper`) against a fresh
+/// server with a breakpoint on `let pre = 1;`, the statement right before
+/// the call, and runs until that breakpoint is hit. Shared by the
+/// stepIn/stepOut tests below, which pick up from there.
+fn launch_stepping_server() -> (DapServer, EventCapture) {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture.clone());
+    let mut server = DapServer::new(input, output);
+
+    let program_path = test_fixtures_dir().join("stepping/src/main.sw");
+    let source_str = program_path.to_string_lossy().to_string();
+
+    let additional_data = serde_json::to_value(AdditionalData {
+        program: source_str.clone(),
+    })
+    .unwrap();
+    let _ = server.handle_command(Command::Launch(LaunchRequestArguments {
+        additional_data: Some(additional_data),
+        ..Default::default()
+    }));
+
+    let _ = server.handle_command(Command::SetBreakpoints(SetBreakpointsArguments {
+        source: Source {
+            path: Some(source_str),
+            ..Default::default()
+        },
+        breakpoints: Some(vec![SourceBreakpoint {
+            line: 13, // `let pre = 1;`, right before the call to `helper`
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }));
+
+    let keep_running = server.handle_launch().expect("launched without error");
+    assert!(keep_running);
+    output_capture.take_event(); // the initial Stopped(Breakpoint) event
+
+    (server, output_capture)
+}
+
+/// `StepIn` should step forward until the call depth increases, i.e. until
+/// execution has actually entered the called function's frame, and report
+/// that as a `Stopped(Step)` event.
+#[test]
+fn test_step_in_stops_on_call_depth_increase() {
+    let (mut server, output_capture) = launch_stepping_server();
+
+    let (result, exit_code) = server.handle_command(Command::StepIn(Default::default()));
+    assert!(result.is_ok());
+    assert!(exit_code.is_none());
+    assert_stopped_next_event(output_capture.take_event());
+}
+
+/// `StepOut` should step forward until the call depth decreases, i.e.
+/// until the called function's frame has actually returned, and report
+/// that as a `Stopped(Step)` event.
+#[test]
+fn test_step_out_stops_on_call_depth_decrease() {
+    let (mut server, output_capture) = launch_stepping_server();
+
+    let _ = server.handle_command(Command::StepIn(Default::default()));
+    output_capture.take_event(); // the Stopped(Step) from entering helper()
+
+    let (result, exit_code) = server.handle_command(Command::StepOut(Default::default()));
+    assert!(result.is_ok());
+    assert!(exit_code.is_none());
+    assert_stopped_next_event(output_capture.take_event());
+}
+
+/// A breakpoint hit while `StepOut` is stepping through a frame should
+/// stop the session there, even though the call depth hasn't decreased to
+/// the step's target yet.
+#[test]
+fn test_step_out_prioritizes_breakpoint_over_depth_target() {
+    let (mut server, output_capture) = launch_stepping_server();
+
+    let _ = server.handle_command(Command::StepIn(Default::default()));
+    output_capture.take_event(); // the Stopped(Step) from entering helper()
+
+    let program_path = test_fixtures_dir().join("stepping/src/main.sw");
+    let (result, _) = server.handle_command(Command::SetBreakpoints(SetBreakpointsArguments {
+        source: Source {
+            path: Some(program_path.to_string_lossy().to_string()),
+            ..Default::default()
+        },
+        breakpoints: Some(vec![SourceBreakpoint {
+            line: 16, // `marker`, reached before the call depth decreases
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }));
+    match result.expect("set breakpoints result") {
+        ResponseBody::SetBreakpoints(res) => assert!(res.breakpoints[0].verified),
+        other => panic!("Expected SetBreakpoints response, got {:?}", other),
+    }
+
+    let (result, exit_code) = server.handle_command(Command::StepOut(Default::default()));
+    assert!(result.is_ok());
+    assert!(exit_code.is_none());
+    assert_stopped_breakpoint_event(output_capture.take_event(), 1);
+}
+
+/// `SetFunctionBreakpoints` should accept breakpoints by Sway function name
+/// and report them as unverified until the program has been built and its
+/// debug symbols resolved, just like source breakpoints.
+#[test]
+fn test_set_function_breakpoints() {
+    let output_capture = EventCapture::default();
+    let input = Box::new(std::io::stdin());
+    let output = Box::new(output_capture);
+    let mut server = DapServer::new(input, output);
+
+    let (result, exit_code) = server.handle_command(Command::SetFunctionBreakpoints(
+        SetFunctionBreakpointsArguments {
+            breakpoints: vec![
+                FunctionBreakpoint {
+                    name: "transfer".into(),
+                    ..Default::default()
+                },
+                FunctionBreakpoint {
+                    name: "mint".into(),
+                    condition: Some("r1 == 0".into()),
+                    ..Default::default()
+                },
+            ],
+        },
+    ));
+    assert!(exit_code.is_none());
+    match result.expect("set function breakpoints result") {
+        ResponseBody::SetFunctionBreakpoints(res) => {
+            assert_eq!(res.breakpoints.len(), 2);
+            // Not resolved until the program is actually built.
+            assert!(res.breakpoints.iter().all(|bp| !bp.verified));
+        }
+        other => panic!("Expected SetFunctionBreakpoints response, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_server_launch_mode() {
     let output_capture = EventCapture::default();
@@ -0,0 +1,52 @@
+use forc_debug::vm::{DebugVm, Instruction, RunState};
+
+fn instr(pc: u64, opcode: &str) -> Instruction {
+    Instruction {
+        pc,
+        opcode: opcode.into(),
+        raw: 0,
+        source_line: None,
+    }
+}
+
+/// `call_depth` should increase on `CALL` and decrease on `RET`/`RETD`, so
+/// stepIn/stepOut can detect frame entry/exit by comparing it before and
+/// after a step.
+#[test]
+fn call_depth_tracks_call_and_return() {
+    let mut vm = DebugVm::new(vec![
+        instr(0, "MOVE"),
+        instr(1, "CALL"),
+        instr(2, "ADD"),
+        instr(3, "RET"),
+        instr(4, "NOOP"),
+    ]);
+
+    assert_eq!(vm.call_depth, 0);
+    vm.step(); // MOVE -> pc 1
+    assert_eq!(vm.call_depth, 0);
+    vm.step(); // CALL -> pc 2, depth 1
+    assert_eq!(vm.call_depth, 1);
+    vm.step(); // ADD -> pc 3
+    assert_eq!(vm.call_depth, 1);
+    vm.step(); // RET -> pc 4, depth 0
+    assert_eq!(vm.call_depth, 0);
+}
+
+/// An `RVRT` instruction should stop the VM with `RunState::Reverted`
+/// carrying the revert reason code from register 1, instead of being treated as
+/// ordinary program completion.
+#[test]
+fn rvrt_reports_reverted_with_reason_code() {
+    let mut vm = DebugVm::new(vec![instr(0, "MOVE"), instr(1, "RVRT")]);
+    vm.registers[1] = 42;
+
+    assert_eq!(vm.step(), None); // MOVE -> pc 1
+    assert_eq!(
+        vm.step(),
+        Some(RunState::Reverted {
+            reason_code: 42,
+            pc: 1
+        })
+    );
+}